@@ -2,9 +2,27 @@
 //!
 //! NOTE: RC4 cipher is known to be cryptographically weak,
 //! and should not be used in security-sensitive scenarios.
+//!
+//! The cipher itself only relies on `core` (array swaps, wrapping
+//! arithmetic and XOR over a `&mut [u8]`), so the crate is `no_std`
+//! and usable in bootloaders, kernels and microcontroller firmware
+//! where no allocator or runtime is available. The `rc4` CLI binary
+//! is a separate, `std`-using target and does not affect this.
+
+#![cfg_attr(not(test), no_std)]
+#![forbid(unsafe_code)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "cipher")]
+mod rustcrypto;
 
 /// Represents RC4 cipher state with provided methods for data
 /// encryption and decryption.
+#[derive(Clone)]
 pub struct RC4 {
     /// Permutations array for `x -> state[x]` permutation.
     state: [u8; 256],
@@ -17,9 +35,33 @@ pub struct RC4 {
     /// generation algorithm (PRGA), used for cipher
     /// keystream/gamma generation.
     j: u8,
+
+    /// The key this instance was key-scheduled with, truncated to the
+    /// first 256 bytes like [`RC4::new`] does. Only kept around for
+    /// [`StreamCipherSeek`](cipher::StreamCipherSeek), which has to
+    /// re-run KSA and fast-forward PRGA from scratch to seek, since
+    /// RC4 has no counter that can be jumped to directly.
+    #[cfg(feature = "cipher")]
+    key: [u8; 256],
+    /// Number of bytes of `key` that are actually part of the key
+    /// (the rest of the 256-byte buffer is unused padding).
+    #[cfg(feature = "cipher")]
+    key_len: u16,
+    /// Absolute number of keystream bytes generated so far, used by
+    /// [`StreamCipherSeek`](cipher::StreamCipherSeek) to report the
+    /// current position. Unlike `i`, this never wraps, since `i` is a
+    /// `u8` PRGA counter that only encodes position modulo 256.
+    #[cfg(feature = "cipher")]
+    keystream_pos: usize,
 }
 
 impl RC4 {
+    /// The number of initial keystream bytes dropped by the
+    /// `RC4-drop[3072]` variant, a common hardened choice (e.g. used
+    /// by some WPA-TKIP deployments) that discards more than ten
+    /// internal states' worth of biased output.
+    pub const RECOMMENDED_DROP: usize = 3072;
+
     /// Returns new `RC4` instance with ready-to-use state.
     ///
     /// Ready-to-use state means that while creating RC4 instance,
@@ -55,14 +97,49 @@ impl RC4 {
             state: [0; 256],
             i: 0,
             j: 0,
+            #[cfg(feature = "cipher")]
+            key: [0; 256],
+            #[cfg(feature = "cipher")]
+            key_len: 0,
+            #[cfg(feature = "cipher")]
+            keystream_pos: 0,
         };
 
+        #[cfg(feature = "cipher")]
+        {
+            let key_len = key.len().min(256);
+            rc4.key[..key_len].copy_from_slice(&key[..key_len]);
+            rc4.key_len = key_len as u16;
+        }
+
         // Perform Key-scheduling algorithm (KSA)
         rc4.key_scheduling_algorithm(key);
 
         rc4
     }
 
+    /// Returns new `RC4` instance, like [`RC4::new`], but additionally
+    /// discards the first `drop` bytes of keystream before the cipher
+    /// is handed back to the caller.
+    ///
+    /// RC4's first few hundred keystream bytes are statistically
+    /// biased (see the Fluhrer–Mantin–Shamir and Mironov
+    /// distinguishers), so deployments that can afford it skip an
+    /// initial prefix of the keystream. [`RC4::RECOMMENDED_DROP`]
+    /// provides the common `RC4-drop[3072]` choice.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - the slice of bytes used as RC4 cipher key.
+    /// * `drop` - the number of initial keystream bytes to generate
+    ///   and discard.
+    pub fn new_with_drop(key: &[u8], drop: usize) -> Self {
+        let mut rc4 = Self::new(key);
+        rc4.skip(drop);
+
+        rc4
+    }
+
     /// Applies Key-scheduling algorithm (KSA) on RC4 instance,
     /// using provided `key`. After KSA is performed, RC4 is ready
     /// to generate keystream/bytes of gamma from the provided `key`.
@@ -115,9 +192,28 @@ impl RC4 {
 
         let keystream_index = self.state[self.i as usize].wrapping_add(self.state[self.j as usize]);
 
+        #[cfg(feature = "cipher")]
+        {
+            self.keystream_pos += 1;
+        }
+
         self.state[keystream_index as usize]
     }
 
+    /// Runs the Pseudo-random generation algorithm (PRGA) `n` times
+    /// and discards the resulting bytes, advancing `i`/`j` and the
+    /// permutation `state` exactly as processing `n` bytes of data
+    /// would, without writing anything.
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - the number of keystream bytes to generate and discard.
+    pub fn skip(&mut self, n: usize) {
+        for _ in 0..n {
+            self.pseudo_random_generation();
+        }
+    }
+
     /// Continuously applies keystream bytes to the bytes of the
     /// provided `data`, XORing each `data` byte with keystream
     /// byte.
@@ -129,4 +225,111 @@ impl RC4 {
             *byte ^= self.pseudo_random_generation();
         });
     }
+
+    /// Applies the keystream to bytes read from `input`, writing each
+    /// XORed byte into the corresponding slot of `output`, without
+    /// requiring `input` to be mutable.
+    ///
+    /// Only as many bytes as the shorter of `input`/`output` are
+    /// processed; any remainder of the longer iterator is left
+    /// untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - iterator over the bytes to apply the keystream to.
+    /// * `output` - iterator over the byte slots the result is
+    ///   written into.
+    pub fn apply_keystream<'a, I, O>(&mut self, input: I, output: O)
+    where
+        I: Iterator<Item = &'a u8>,
+        O: Iterator<Item = &'a mut u8>,
+    {
+        input.zip(output).for_each(|(input_byte, output_byte)| {
+            *output_byte = input_byte ^ self.pseudo_random_generation();
+        });
+    }
+
+    /// Returns a freshly allocated `Vec<u8>` of `input`'s length,
+    /// containing `input` with the keystream applied.
+    ///
+    /// Unlike [`RC4::xor_keystream_with`] and [`RC4::apply_keystream`],
+    /// this does not mutate `self`: it runs the keystream over a
+    /// clone of the current state, so the same configured cipher
+    /// instance can process multiple independent messages from the
+    /// same key-scheduled starting point.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - the bytes to apply the keystream to.
+    #[cfg(feature = "alloc")]
+    pub fn process(&self, input: &[u8]) -> Vec<u8> {
+        let mut output = alloc::vec![0u8; input.len()];
+        self.clone()
+            .apply_keystream(input.iter(), output.iter_mut());
+
+        output
+    }
+
+    /// Overwrites the cipher's `state` with zeros and resets the PRGA
+    /// counters `i`/`j` to zero, clearing the key-derived permutation
+    /// from memory.
+    ///
+    /// The permuted `state` is effectively recoverable key material for
+    /// as long as it stays resident, so long-lived processes that are
+    /// done with a cipher instance should call `reset` once they no
+    /// longer need it (or rely on [`Drop`] by enabling the `zeroize`
+    /// feature, which scrubs the state automatically).
+    ///
+    /// As in the reference implementations, this is only a
+    /// best-effort measure: it cannot guarantee removal of copies the
+    /// compiler or OS may already have spilled to registers, the
+    /// stack, swap space, or core dumps.
+    pub fn reset(&mut self) {
+        self.state = [0; 256];
+        self.i = 0;
+        self.j = 0;
+
+        // The `cipher` feature keeps a copy of the raw, unpermuted key
+        // around for seeking (see `stored_key`), which is far more
+        // sensitive than the permuted `state` above, so it must be
+        // cleared here too, same as `Drop` already does.
+        #[cfg(feature = "cipher")]
+        {
+            self.key = [0; 256];
+            self.key_len = 0;
+            self.keystream_pos = 0;
+        }
+    }
+
+    /// Returns the key this instance was constructed with, as stored
+    /// for seeking. See the `key`/`key_len` fields.
+    #[cfg(feature = "cipher")]
+    fn stored_key(&self) -> &[u8] {
+        &self.key[..self.key_len as usize]
+    }
+}
+
+/// Scrubs the cipher's key-derived state when it is dropped, using
+/// [`zeroize::Zeroize`] so the writes aren't optimized away as dead
+/// stores. This is best-effort for the same reasons documented on
+/// [`RC4::reset`].
+#[cfg(feature = "zeroize")]
+impl Drop for RC4 {
+    fn drop(&mut self) {
+        use zeroize::Zeroize;
+
+        self.state.zeroize();
+        self.i.zeroize();
+        self.j.zeroize();
+
+        // The `cipher` feature keeps a copy of the raw, unpermuted key
+        // around for seeking (see `RC4::stored_key`), which is far
+        // more sensitive than the permuted `state` above, so it must
+        // be scrubbed too.
+        #[cfg(feature = "cipher")]
+        {
+            self.key.zeroize();
+            self.key_len.zeroize();
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use clap::Parser;
+use clap::{ArgGroup, Parser};
 use std::io::{Read, Write};
 
 // Clap usage notes:
@@ -8,19 +8,98 @@ use std::io::{Read, Write};
 // Documentation of the struct field becomes CLI argument/option
 // description.
 
-/// Applies RC4 cipher to input file data and writes the result
-/// to output file.
+/// Applies RC4 cipher to input data and writes the result to output.
+///
+/// `--in`/`--out` accept `-` to mean stdin/stdout, so the tool can sit
+/// in a Unix pipeline, e.g. `cat data | rc4 -k key.bin -i - -o - | gzip`.
 #[derive(Parser)] // Derive clap::Parser trait implementation for Args struct
+#[command(group(
+    ArgGroup::new("key_source")
+        .required(true)
+        .args(["key", "key_hex", "key_string"]),
+))]
 struct Args {
-    /// Path to the file with input data
+    /// Path to the file with input data, or `-` for stdin
     #[arg(short, long = "in")]
-    input: std::path::PathBuf,
-    /// Path to the file to place output data to
+    input: String,
+    /// Path to the file to place output data to, or `-` for stdout
     #[arg(short, long = "out")]
-    output: std::path::PathBuf,
+    output: String,
     /// Path to the file with key
     #[arg(short, long)]
-    key: std::path::PathBuf,
+    key: Option<std::path::PathBuf>,
+    /// Key given directly as a hex string, e.g. `deadbeef`
+    #[arg(long)]
+    key_hex: Option<String>,
+    /// Key given directly as a string
+    #[arg(long)]
+    key_string: Option<String>,
+    /// Overwrite the output file if it already exists
+    #[arg(long)]
+    force: bool,
+    /// Number of initial keystream bytes to discard (RC4-drop[N]),
+    /// to skip RC4's statistically biased early output
+    #[arg(long, default_value_t = 0)]
+    drop: usize,
+}
+
+/// Decodes a hex string (as given to `--key-hex`) into its raw bytes.
+fn decode_hex(hex: &str) -> std::io::Result<Vec<u8>> {
+    let invalid_hex =
+        || std::io::Error::new(std::io::ErrorKind::InvalidInput, "key-hex is not valid hex");
+
+    // Hex digits are always single-byte ASCII, so work over raw bytes
+    // rather than `str` byte-offset slicing: a non-ASCII character
+    // (e.g. multi-byte UTF-8) would otherwise land an offset on a
+    // byte that isn't a char boundary and panic.
+    if !hex.is_ascii() || !hex.len().is_multiple_of(2) {
+        return Err(invalid_hex());
+    }
+
+    hex.as_bytes()
+        .chunks_exact(2)
+        .map(|pair| {
+            let pair = std::str::from_utf8(pair).map_err(|_| invalid_hex())?;
+            u8::from_str_radix(pair, 16).map_err(|_| invalid_hex())
+        })
+        .collect()
+}
+
+/// Opens `path` for reading, or stdin if `path` is `-`.
+fn open_input(path: &str) -> std::io::Result<Box<dyn Read>> {
+    if path == "-" {
+        return Ok(Box::new(std::io::stdin()));
+    }
+
+    match std::fs::File::open(path) {
+        Ok(file) => Ok(Box::new(file)),
+        Err(err) => {
+            eprint!("Cannot open input file: ");
+            Err(err)
+        }
+    }
+}
+
+/// Opens `path` for writing, or stdout if `path` is `-`. Unless
+/// `force` is set, refuses to overwrite an existing file, to avoid
+/// accidental data loss.
+fn open_output(path: &str, force: bool) -> std::io::Result<Box<dyn Write>> {
+    if path == "-" {
+        return Ok(Box::new(std::io::stdout()));
+    }
+
+    let file = if force {
+        std::fs::File::create(path)
+    } else {
+        std::fs::File::create_new(path)
+    };
+    match file {
+        Ok(file) => Ok(Box::new(file)),
+        Err(err) => {
+            eprint!("Cannot create output file: ");
+            Err(err)
+        }
+    }
 }
 
 // We can return Result<(), E> variants from main() function,
@@ -30,44 +109,41 @@ struct Args {
 fn main() -> std::io::Result<()> {
     let args = Args::parse();
 
-    let input = match std::fs::File::open(args.input) {
-        Ok(file) => file,
-        Err(err) => {
-            eprint!("Cannot open input file: ");
-            return Err(err);
-        }
-    };
-    // Add buffering to reading from input file. It should be faster
+    // Add buffering to reading from input. It should be faster
     // than reading small amount of bytes separately and not such memory
     // consuming as reading entire file to string/vector
-    let mut input = std::io::BufReader::new(input);
+    let mut input = std::io::BufReader::new(open_input(&args.input)?);
 
-    let key = match std::fs::read(args.key) {
-        Ok(key) => key,
-        Err(err) => {
-            eprint!("Cannot read from key file: ");
-            return Err(err);
+    let key = if let Some(path) = args.key {
+        match std::fs::read(path) {
+            Ok(key) => key,
+            Err(err) => {
+                eprint!("Cannot read from key file: ");
+                return Err(err);
+            }
         }
+    } else if let Some(key_hex) = args.key_hex {
+        decode_hex(&key_hex)?
+    } else if let Some(key_string) = args.key_string {
+        key_string.into_bytes()
+    } else {
+        // clap's "key_source" group requires exactly one of
+        // key/key_hex/key_string, so one of the above always matches
+        unreachable!("clap guarantees a key source is provided")
     };
+    // These go to stderr, not stdout: with `--out -`, stdout is the
+    // ciphertext stream itself, and a warning written there would be
+    // interleaved with it, corrupting the output.
     if key.len() < 256 {
-        println!("Warning: key is less than 256 bytes long, some bytes might be reused");
+        eprintln!("Warning: key is less than 256 bytes long, some bytes might be reused");
     }
     if key.len() > 256 {
-        println!("Warning: key is more than 256 bytes long, these bytes will not be used");
+        eprintln!("Warning: key is more than 256 bytes long, these bytes will not be used");
     }
 
-    // Require that output file shouldn't exist before processing
-    // for avoiding accidental data loss
-    let output = match std::fs::File::create_new(args.output) {
-        Ok(file) => file,
-        Err(err) => {
-            eprint!("Cannot create output file: ");
-            return Err(err);
-        }
-    };
-    let mut output = std::io::BufWriter::new(output);
+    let mut output = std::io::BufWriter::new(open_output(&args.output, args.force)?);
 
-    let mut rc4 = rc4_rs::RC4::new(key.as_slice());
+    let mut rc4 = rc4_rs::RC4::new_with_drop(key.as_slice(), args.drop);
     let mut processed_data = [0u8; 256];
 
     loop {
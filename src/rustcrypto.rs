@@ -0,0 +1,69 @@
+//! Implements the [RustCrypto `cipher`](https://docs.rs/cipher) trait
+//! vocabulary for [`RC4`], gated behind the `cipher` feature, so this
+//! crate drops into code that already speaks those traits (e.g.
+//! generic wrappers over `StreamCipher`).
+//!
+//! RC4 has no counter that can be jumped to directly, so
+//! [`StreamCipherSeek`] seeks to an absolute byte position `p` by
+//! re-running KSA from the stored key and then calling
+//! [`RC4::skip`] with `p`, which runs PRGA `p` times, to fast-forward the permutation/counters
+//! back to that offset. This is why [`RC4`] keeps a copy of the key
+//! around when the `cipher` feature is enabled.
+
+use cipher::inout::InOutBuf;
+use cipher::{
+    consts::U256, Key, KeyInit, KeySizeUser, OverflowError, SeekNum, StreamCipher,
+    StreamCipherError, StreamCipherSeek,
+};
+
+use crate::RC4;
+
+/// RustCrypto key-compatibility note: this treats the RC4 key as a
+/// fixed 256-byte `cipher::Key`, matching the maximum key length
+/// [`RC4::new`] accepts. Use [`RC4::new`] directly when a
+/// variable-length (non-256-byte) key is needed outside the `cipher`
+/// trait vocabulary.
+impl KeySizeUser for RC4 {
+    type KeySize = U256;
+}
+
+impl KeyInit for RC4 {
+    fn new(key: &Key<Self>) -> Self {
+        RC4::new(key.as_slice())
+    }
+}
+
+impl StreamCipher for RC4 {
+    fn try_apply_keystream_inout(
+        &mut self,
+        mut buf: InOutBuf<'_, '_, u8>,
+    ) -> Result<(), StreamCipherError> {
+        let (input, output) = buf.get_in_out();
+        self.apply_keystream(input.iter(), output.iter_mut());
+
+        Ok(())
+    }
+}
+
+impl StreamCipherSeek for RC4 {
+    fn try_current_pos<T: SeekNum>(&self) -> Result<T, OverflowError> {
+        // RC4 has no notion of a "block", so every keystream byte is
+        // its own block of size 1 and the byte offset within it is
+        // always 0; `keystream_pos` is the absolute position.
+        T::from_block_byte(self.keystream_pos as u64, 0, 1)
+    }
+
+    fn try_seek<T: SeekNum>(&mut self, pos: T) -> Result<(), StreamCipherError> {
+        let (block, byte): (u64, u8) = pos
+            .into_block_byte(1)
+            .map_err(|_: OverflowError| StreamCipherError)?;
+        let pos = block + byte as u64;
+
+        let mut rc4 = RC4::new(self.stored_key());
+        rc4.skip(pos as usize);
+
+        *self = rc4;
+
+        Ok(())
+    }
+}
@@ -37,3 +37,92 @@ fn encrypt_secret_attack_at_dawn() {
     ];
     assert_eq!(data.as_slice(), ciphertext);
 }
+
+#[test]
+fn apply_keystream_matches_xor_keystream_with() {
+    let mut rc4_in_place = rc4_rs::RC4::new("Key".as_bytes());
+    let mut in_place_data = Vec::from("Plaintext");
+    rc4_in_place.xor_keystream_with(&mut in_place_data);
+
+    let mut rc4_separate = rc4_rs::RC4::new("Key".as_bytes());
+    let input = Vec::from("Plaintext");
+    let mut output = vec![0u8; input.len()];
+    rc4_separate.apply_keystream(input.iter(), output.iter_mut());
+
+    assert_eq!(output, in_place_data);
+}
+
+#[test]
+fn reset_makes_cipher_behave_like_a_freshly_zeroed_state() {
+    let mut rc4 = rc4_rs::RC4::new("Key".as_bytes());
+
+    let mut data = Vec::from("Plaintext");
+    rc4.xor_keystream_with(&mut data);
+    rc4.reset();
+
+    // After reset(), the permutation is all zeros and the counters
+    // are back to zero, so every keystream byte is simply 0.
+    let mut zeroed_keystream = vec![0u8; 4];
+    rc4.xor_keystream_with(&mut zeroed_keystream);
+    assert_eq!(zeroed_keystream, [0, 0, 0, 0]);
+}
+
+#[test]
+fn new_with_drop_matches_new_plus_skip() {
+    let mut rc4_skip = rc4_rs::RC4::new("Key".as_bytes());
+    rc4_skip.skip(16);
+
+    let mut rc4_drop = rc4_rs::RC4::new_with_drop("Key".as_bytes(), 16);
+
+    let mut data_skip = Vec::from("Plaintext");
+    rc4_skip.xor_keystream_with(&mut data_skip);
+
+    let mut data_drop = Vec::from("Plaintext");
+    rc4_drop.xor_keystream_with(&mut data_drop);
+
+    assert_eq!(data_skip, data_drop);
+}
+
+#[test]
+fn new_with_drop_changes_the_keystream() {
+    let mut rc4 = rc4_rs::RC4::new("Key".as_bytes());
+    let mut rc4_dropped = rc4_rs::RC4::new_with_drop("Key".as_bytes(), rc4_rs::RC4::RECOMMENDED_DROP);
+
+    let mut data = Vec::from("Plaintext");
+    rc4.xor_keystream_with(&mut data);
+
+    let mut data_dropped = Vec::from("Plaintext");
+    rc4_dropped.xor_keystream_with(&mut data_dropped);
+
+    assert_ne!(data, data_dropped);
+}
+
+#[cfg(feature = "cipher")]
+#[test]
+fn stream_cipher_seek_matches_skip() {
+    use cipher::{KeyInit, StreamCipher, StreamCipherSeek};
+
+    let mut rc4_skip = rc4_rs::RC4::new("Key".as_bytes());
+    rc4_skip.skip(5);
+    let mut data_skip = Vec::from("Plaintext");
+    rc4_skip.xor_keystream_with(&mut data_skip);
+
+    let mut rc4_seek = rc4_rs::RC4::new("Key".as_bytes());
+    rc4_seek.try_seek(5u64).unwrap();
+    let mut data_seek = Vec::from("Plaintext");
+    rc4_seek.try_apply_keystream(&mut data_seek).unwrap();
+
+    assert_eq!(data_skip, data_seek);
+}
+
+#[test]
+fn process_does_not_mutate_cipher_and_can_be_reused() {
+    let rc4 = rc4_rs::RC4::new("Key".as_bytes());
+
+    let first = rc4.process("Plaintext".as_bytes());
+    let second = rc4.process("Plaintext".as_bytes());
+
+    let ciphertext = [0xBB, 0xF3, 0x16, 0xE8, 0xD9, 0x40, 0xAF, 0x0A, 0xD3];
+    assert_eq!(first, ciphertext);
+    assert_eq!(second, ciphertext);
+}